@@ -1,7 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
 use std::fmt;
+use std::hash::{DefaultHasher, Hasher};
+use std::io::{self, Read, Write};
 use std::ops::Deref;
 
+pub const MAX_STANDARD_TX_WEIGHT: u64 = 400_000;
+pub const TRUC_MAX_VSIZE: u64 = 10_000;
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct CompactSize {
     pub value: u64,
@@ -11,6 +18,10 @@ pub struct CompactSize {
 pub enum BitcoinError {
     InsufficientBytes,
     InvalidFormat,
+    InsufficientFunds,
+    InvalidInputIndex,
+    LockTimeNotSatisfied { lock_time: u32, input_index: usize },
+    SequenceLockNotSatisfied { input_index: usize },
 }
 
 impl CompactSize {
@@ -69,9 +80,35 @@ impl CompactSize {
 
         Ok((CompactSize::new(value), consumed))
     }
+
+    pub fn is_canonical(bytes: &[u8]) -> bool {
+        match CompactSize::from_bytes(bytes) {
+            Ok((compact_size, consumed)) => {
+                consumed == bytes.len() && compact_size.to_bytes() == bytes
+            }
+            Err(_) => false,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+pub fn write_var_bytes(data: &[u8], buf: &mut Vec<u8>) {
+    buf.extend(CompactSize::new(data.len() as u64).to_bytes());
+    buf.extend(data);
+}
+
+pub fn read_var_bytes(bytes: &[u8]) -> Result<(Vec<u8>, usize), BitcoinError> {
+    let (length, consumed) = CompactSize::from_bytes(bytes)?;
+    let end = usize::try_from(length.value)
+        .ok()
+        .and_then(|length| consumed.checked_add(length))
+        .ok_or(BitcoinError::InsufficientBytes)?;
+    let data = bytes
+        .get(consumed..end)
+        .ok_or(BitcoinError::InsufficientBytes)?;
+    Ok((data.to_vec(), end))
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct Txid(pub [u8; 32]);
 
 impl Serialize for Txid {
@@ -100,7 +137,7 @@ impl<'de> Deserialize<'de> for Txid {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub struct OutPoint {
     pub txid: Txid,
     pub vout: u32,
@@ -136,26 +173,225 @@ pub struct Script {
     pub bytes: Vec<u8>,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ScriptType {
+    P2PK,
+    P2PKH,
+    P2SH,
+    P2WPKH,
+    P2WSH,
+    P2TR,
+    P2A,
+    OpReturn,
+    NonStandard,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Instruction<'a> {
+    PushBytes(&'a [u8]),
+    Op(u8),
+}
+
+pub struct Instructions<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Iterator for Instructions<'a> {
+    type Item = Result<Instruction<'a>, BitcoinError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let opcode = *self.bytes.get(self.position)?;
+        self.position += 1;
+        let (length_size, length) = match opcode {
+            0x01..=0x4B => (0, opcode as usize),
+            0x4C..=0x4E => {
+                let length_size = 1 << (opcode - 0x4C);
+                let Some(length_bytes) = self.bytes.get(self.position..self.position + length_size)
+                else {
+                    self.position = self.bytes.len();
+                    return Some(Err(BitcoinError::InsufficientBytes));
+                };
+                let mut buf = [0u8; 4];
+                buf[..length_size].copy_from_slice(length_bytes);
+                (length_size, u32::from_le_bytes(buf) as usize)
+            }
+            0x00 => return Some(Ok(Instruction::PushBytes(&[]))),
+            _ => return Some(Ok(Instruction::Op(opcode))),
+        };
+        let start = self.position + length_size;
+        let Some(data) = self.bytes.get(start..start + length) else {
+            self.position = self.bytes.len();
+            return Some(Err(BitcoinError::InsufficientBytes));
+        };
+        self.position = start + length;
+        Some(Ok(Instruction::PushBytes(data)))
+    }
+}
+
+fn push_data(script: &mut Vec<u8>, data: &[u8]) {
+    match data.len() {
+        0..=0x4B => script.push(data.len() as u8),
+        0x4C..=0xFF => script.extend([0x4C, data.len() as u8]),
+        0x100..=0xFFFF => {
+            script.push(0x4D);
+            script.extend((data.len() as u16).to_le_bytes());
+        }
+        _ => {
+            script.push(0x4E);
+            script.extend((data.len() as u32).to_le_bytes());
+        }
+    }
+    script.extend(data);
+}
+
+fn is_minimal_push(opcode: u8, data: &[u8]) -> bool {
+    match data {
+        [] => opcode == 0x00,
+        [1..=16] | [0x81] => false,
+        _ if data.len() <= 0x4B => opcode as usize == data.len(),
+        _ if data.len() <= 0xFF => opcode == 0x4C,
+        _ if data.len() <= 0xFFFF => opcode == 0x4D,
+        _ => true,
+    }
+}
+
+/// Checks for a DER-encoded ECDSA signature followed by a sighash byte.
+fn is_signature_push(data: &[u8]) -> bool {
+    if data.len() < 9 || data.len() > 73 || data[0] != 0x30 || data[1] as usize != data.len() - 3 {
+        return false;
+    }
+    let r_len = data[3] as usize;
+    if data[2] != 0x02 || 5 + r_len >= data.len() || data[4 + r_len] != 0x02 {
+        return false;
+    }
+    let s_len = data[5 + r_len] as usize;
+    6 + r_len + s_len == data.len() - 1
+}
+
+fn parse_witness_program(bytes: &[u8]) -> Option<(u8, &[u8])> {
+    if bytes.len() < 4 || bytes.len() > 42 {
+        return None;
+    }
+    let version = match bytes[0] {
+        0x00 => 0,
+        0x51..=0x60 => bytes[0] - 0x50,
+        _ => return None,
+    };
+    if bytes[1] as usize != bytes.len() - 2 {
+        return None;
+    }
+    Some((version, &bytes[2..]))
+}
+
 impl Script {
     pub fn new(bytes: Vec<u8>) -> Self {
         Script { bytes }
     }
 
+    /// Returns the witness version and program if this is a witness output script.
+    pub fn witness_program(&self) -> Option<(u8, &[u8])> {
+        parse_witness_program(&self.bytes)
+    }
+
+    pub fn instructions(&self) -> Instructions<'_> {
+        Instructions {
+            bytes: &self.bytes,
+            position: 0,
+        }
+    }
+
+    pub fn script_type(&self) -> ScriptType {
+        let bytes = &self.bytes;
+        match bytes.as_slice() {
+            [0x76, 0xA9, 0x14, .., 0x88, 0xAC] if bytes.len() == 25 => return ScriptType::P2PKH,
+            [0xA9, 0x14, .., 0x87] if bytes.len() == 23 => return ScriptType::P2SH,
+            [0x6A, ..] => return ScriptType::OpReturn,
+            _ => {}
+        }
+        if self.p2pk_pubkey().is_some() {
+            return ScriptType::P2PK;
+        }
+        match self.witness_program() {
+            Some((0, program)) if program.len() == 20 => ScriptType::P2WPKH,
+            Some((0, program)) if program.len() == 32 => ScriptType::P2WSH,
+            Some((1, program)) if program.len() == 32 => ScriptType::P2TR,
+            Some((1, [0x4E, 0x73])) => ScriptType::P2A,
+            _ => ScriptType::NonStandard,
+        }
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = self.bytes.clone();
-        let length = CompactSize::new(bytes.len() as u64);
-        let mut result = length.to_bytes();
-        result.append(&mut bytes);
+        let mut result = Vec::with_capacity(self.bytes.len() + 1);
+        write_var_bytes(&self.bytes, &mut result);
         result
     }
 
     pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
-        let (length, consumed) = CompactSize::from_bytes(bytes)?;
-        if bytes.len() < consumed + length.value as usize {
-            return Err(BitcoinError::InsufficientBytes);
+        let (script_bytes, consumed) = read_var_bytes(bytes)?;
+        Ok((Script::new(script_bytes), consumed))
+    }
+
+    pub fn taproot_output_key(&self) -> Option<[u8; 32]> {
+        match self.witness_program() {
+            Some((1, program)) if program.len() == 32 => program.try_into().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn new_p2sh_multisig_scriptsig(signatures: &[Vec<u8>], redeem_script: &Script) -> Script {
+        // OP_0 dummy consumed by OP_CHECKMULTISIG's off-by-one pop.
+        let mut bytes = vec![0x00];
+        for signature in signatures {
+            push_data(&mut bytes, signature);
+        }
+        push_data(&mut bytes, &redeem_script.bytes);
+        Script::new(bytes)
+    }
+
+    pub fn is_push_only(&self) -> bool {
+        self.instructions().all(|instruction| {
+            matches!(
+                instruction,
+                Ok(Instruction::PushBytes(_)) | Ok(Instruction::Op(0x4F..=0x60))
+            )
+        })
+    }
+
+    pub fn has_non_minimal_push(&self) -> bool {
+        let mut instructions = self.instructions();
+        loop {
+            let opcode_position = instructions.position;
+            match instructions.next() {
+                Some(Ok(Instruction::PushBytes(data))) => {
+                    if !is_minimal_push(self.bytes[opcode_position], data) {
+                        return true;
+                    }
+                }
+                Some(Ok(Instruction::Op(_))) => {}
+                Some(Err(_)) | None => return false,
+            }
+        }
+    }
+
+    pub fn p2pk_pubkey(&self) -> Option<&[u8]> {
+        match self.bytes.as_slice() {
+            [0x21, pubkey @ .., 0xAC] if pubkey.len() == 33 => Some(pubkey),
+            [0x41, pubkey @ .., 0xAC] if pubkey.len() == 65 => Some(pubkey),
+            _ => None,
         }
-        let script_bytes = bytes[consumed..(consumed + length.value as usize)].to_vec();
-        Ok((Script::new(script_bytes), consumed + length.value as usize))
+    }
+
+    /// Heuristic: counts DER-signature-shaped pushes, or zero if the script is not push-only.
+    pub fn count_signatures(&self) -> usize {
+        if !self.is_push_only() {
+            return 0;
+        }
+        self.instructions()
+            .filter(|instruction| {
+                matches!(instruction, Ok(Instruction::PushBytes(data)) if is_signature_push(data))
+            })
+            .count()
     }
 }
 
@@ -193,16 +429,132 @@ impl TransactionInput {
     pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
         let (previous_output, consumed) = OutPoint::from_bytes(bytes)?;
         let (script_sig, consumed_script) = Script::from_bytes(&bytes[consumed..])?;
-        let sequence = u32::from_le_bytes(
-            bytes[consumed + consumed_script..consumed + consumed_script + 4]
-                .try_into()
-                .unwrap(),
-        );
+        let start = consumed + consumed_script;
+        let sequence_bytes = bytes
+            .get(start..start + 4)
+            .ok_or(BitcoinError::InsufficientBytes)?;
+        let sequence = u32::from_le_bytes(sequence_bytes.try_into().unwrap());
         Ok((
             TransactionInput::new(previous_output, script_sig, sequence),
-            consumed + consumed_script + 4,
+            start + 4,
         ))
     }
+
+    /// Ignores any change in the size of the transaction's input count.
+    pub fn incremental_weight(&self) -> u64 {
+        self.serialized_size() as u64 * 4
+    }
+
+    pub fn serialized_size(&self) -> usize {
+        let script_len = CompactSize::new(self.script_sig.len() as u64);
+        36 + script_len.to_bytes().len() + self.script_sig.len() + 4
+    }
+
+    pub fn nested_witness_program(&self) -> Option<&[u8]> {
+        let mut instructions = self.script_sig.instructions();
+        let Some(Ok(Instruction::PushBytes(redeem_script))) = instructions.next() else {
+            return None;
+        };
+        if instructions.next().is_some() {
+            return None;
+        }
+        parse_witness_program(redeem_script).map(|_| redeem_script)
+    }
+
+    pub fn is_p2sh_segwit(&self) -> bool {
+        self.nested_witness_program().is_some()
+    }
+
+    pub fn relative_lock_time(&self) -> Option<RelativeLockTime> {
+        RelativeLockTime::from_sequence(self.sequence)
+    }
+
+    /// Returns the minimum block height and median time past at which this input's
+    /// BIP68 lock is satisfied; a zero component means no constraint. As in Core,
+    /// `utxo_confirmation_time` is the median time past of the block before the
+    /// one that confirmed the output, not of the confirming block itself.
+    pub fn spendable_at(
+        &self,
+        utxo_confirmation_height: u32,
+        utxo_confirmation_time: u32,
+    ) -> (u32, u32) {
+        match self.relative_lock_time() {
+            Some(RelativeLockTime::Blocks(blocks)) => {
+                (utxo_confirmation_height.saturating_add(blocks as u32), 0)
+            }
+            Some(RelativeLockTime::Time(units)) => {
+                (0, utxo_confirmation_time.saturating_add(units as u32 * 512))
+            }
+            None => (0, 0),
+        }
+    }
+}
+
+/// BIP69 input order: previous txid compared in display (reversed) byte order, then vout.
+fn bip69_input_cmp(a: &TransactionInput, b: &TransactionInput) -> Ordering {
+    a.previous_output
+        .txid
+        .0
+        .iter()
+        .rev()
+        .cmp(b.previous_output.txid.0.iter().rev())
+        .then(a.previous_output.vout.cmp(&b.previous_output.vout))
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RelativeLockTime {
+    Blocks(u16),
+    /// In units of 512 seconds.
+    Time(u16),
+}
+
+impl RelativeLockTime {
+    const DISABLE_FLAG: u32 = 1 << 31;
+    const TYPE_FLAG: u32 = 1 << 22;
+    const VALUE_MASK: u32 = 0x0000FFFF;
+
+    /// Decodes a BIP68 relative timelock from an input's sequence number.
+    pub fn from_sequence(sequence: u32) -> Option<Self> {
+        if sequence & Self::DISABLE_FLAG != 0 {
+            return None;
+        }
+        let value = (sequence & Self::VALUE_MASK) as u16;
+        if sequence & Self::TYPE_FLAG != 0 {
+            Some(RelativeLockTime::Time(value))
+        } else {
+            Some(RelativeLockTime::Blocks(value))
+        }
+    }
+}
+
+pub const LOCK_TIME_THRESHOLD: u32 = 500_000_000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LockTime {
+    Blocks(u32),
+    Seconds(u32),
+}
+
+impl LockTime {
+    pub fn from_height(height: u32) -> Result<Self, BitcoinError> {
+        if height >= LOCK_TIME_THRESHOLD {
+            return Err(BitcoinError::InvalidFormat);
+        }
+        Ok(LockTime::Blocks(height))
+    }
+
+    pub fn from_time(time: u32) -> Result<Self, BitcoinError> {
+        if time < LOCK_TIME_THRESHOLD {
+            return Err(BitcoinError::InvalidFormat);
+        }
+        Ok(LockTime::Seconds(time))
+    }
+
+    pub fn to_u32(&self) -> u32 {
+        match self {
+            LockTime::Blocks(value) | LockTime::Seconds(value) => *value,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -259,6 +611,584 @@ impl BitcoinTransaction {
             total_consumed + 4,
         ))
     }
+
+    pub fn input_timelocks(
+        &self,
+    ) -> impl Iterator<Item = (OutPoint, Option<RelativeLockTime>)> + '_ {
+        self.inputs.iter().map(|input| {
+            (
+                input.previous_output.clone(),
+                RelativeLockTime::from_sequence(input.sequence),
+            )
+        })
+    }
+
+    /// Transactions here carry no witness data, so every byte counts four weight units.
+    pub fn weight(&self) -> u64 {
+        self.to_bytes().len() as u64 * 4
+    }
+
+    pub fn exceeds_standard_weight(&self) -> bool {
+        self.weight() > MAX_STANDARD_TX_WEIGHT
+    }
+
+    pub fn spent_outpoints(&self) -> impl Iterator<Item = &OutPoint> {
+        self.inputs.iter().map(|input| &input.previous_output)
+    }
+
+    pub fn is_coinbase(&self) -> bool {
+        self.inputs.len() == 1
+            && self.inputs[0].previous_output.txid.0 == [0u8; 32]
+            && self.inputs[0].previous_output.vout == 0xFFFFFFFF
+    }
+
+    pub fn coinbase_scriptsig(&self) -> Option<&[u8]> {
+        if self.is_coinbase() {
+            Some(&self.inputs[0].script_sig.bytes)
+        } else {
+            None
+        }
+    }
+
+    /// Does nothing if this is not a coinbase transaction.
+    pub fn set_coinbase_scriptsig(&mut self, bytes: Vec<u8>) {
+        if self.is_coinbase() {
+            self.inputs[0].script_sig = Script::new(bytes);
+        }
+    }
+
+    pub fn vsize(&self) -> u64 {
+        self.weight().div_ceil(4)
+    }
+
+    /// Saturates at the `i64` bounds rather than wrapping.
+    pub fn fee_overpayment(&self, actual_fee: u64, target_feerate_sat_vb: f64) -> i64 {
+        let target_fee = (self.vsize() as f64 * target_feerate_sat_vb).ceil() as i128;
+        (actual_fee as i128 - target_fee).clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    /// Heuristic: only scriptSig pushes shaped like DER signatures are reported.
+    pub fn signature_pushes(&self) -> Vec<(usize, Vec<u8>)> {
+        let mut signatures = Vec::new();
+        for (index, input) in self.inputs.iter().enumerate() {
+            for instruction in input.script_sig.instructions() {
+                match instruction {
+                    Ok(Instruction::PushBytes(data)) if is_signature_push(data) => {
+                        signatures.push((index, data.to_vec()))
+                    }
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+        }
+        signatures
+    }
+
+    pub fn to_bytes_with_input_scripts_blanked(&self, except: Option<usize>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(&self.version.to_le_bytes());
+        bytes.extend(CompactSize::new(self.inputs.len() as u64).to_bytes());
+        for (index, input) in self.inputs.iter().enumerate() {
+            if except == Some(index) {
+                bytes.extend(input.to_bytes());
+            } else {
+                bytes.extend(input.previous_output.to_bytes());
+                bytes.push(0x00);
+                bytes.extend(&input.sequence.to_le_bytes());
+            }
+        }
+        bytes.extend(&self.lock_time.to_le_bytes());
+        bytes
+    }
+
+    pub fn set_lock_time(&mut self, lock_time: LockTime) {
+        self.lock_time = lock_time.to_u32();
+    }
+
+    pub fn hex_dump(&self) -> String {
+        let mut lines = vec![
+            format!("version: {}", hex::encode(self.version.to_le_bytes())),
+            format!(
+                "vin_count: {}",
+                hex::encode(CompactSize::new(self.inputs.len() as u64).to_bytes())
+            ),
+        ];
+        for (index, input) in self.inputs.iter().enumerate() {
+            let script_len = CompactSize::new(input.script_sig.len() as u64);
+            lines.push(format!(
+                "vin[{index}].txid: {}",
+                hex::encode(input.previous_output.txid.0)
+            ));
+            lines.push(format!(
+                "vin[{index}].vout: {}",
+                hex::encode(input.previous_output.vout.to_le_bytes())
+            ));
+            lines.push(format!(
+                "vin[{index}].script_len: {}",
+                hex::encode(script_len.to_bytes())
+            ));
+            lines.push(format!(
+                "vin[{index}].script_sig: {}",
+                hex::encode(&input.script_sig.bytes)
+            ));
+            lines.push(format!(
+                "vin[{index}].sequence: {}",
+                hex::encode(input.sequence.to_le_bytes())
+            ));
+        }
+        lines.push(format!(
+            "lock_time: {}",
+            hex::encode(self.lock_time.to_le_bytes())
+        ));
+        lines.join("\n")
+    }
+
+    pub fn from_parts(
+        version_bytes: [u8; 4],
+        inputs: Vec<TransactionInput>,
+        lock_time_bytes: [u8; 4],
+    ) -> Self {
+        BitcoinTransaction::new(
+            u32::from_le_bytes(version_bytes),
+            inputs,
+            u32::from_le_bytes(lock_time_bytes),
+        )
+    }
+
+    /// Non-cryptographic cache key; not guaranteed stable across Rust releases.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&self.to_bytes());
+        hasher.finish()
+    }
+
+    pub fn prevouts_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.inputs.len() * 36);
+        for input in &self.inputs {
+            bytes.extend(input.previous_output.to_bytes());
+        }
+        bytes
+    }
+
+    pub fn sequences_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.inputs.len() * 4);
+        for input in &self.inputs {
+            bytes.extend(&input.sequence.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// BIP125: any input with a sequence below 0xFFFFFFFE opts in to replacement.
+    pub fn signals_rbf(&self) -> bool {
+        self.inputs.iter().any(|input| input.sequence < 0xFFFFFFFE)
+    }
+
+    /// Output-related checks (dust, OP_RETURN size) are not covered since
+    /// transactions here have no outputs.
+    pub fn lint(&self) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        if !(1..=3).contains(&self.version) {
+            warnings.push(Warning::NonStandardVersion(self.version));
+        }
+        if self.signals_rbf() {
+            warnings.push(Warning::SignalsRbf);
+        }
+        for (index, input) in self.inputs.iter().enumerate() {
+            if !input.script_sig.is_push_only() {
+                warnings.push(Warning::NonPushOnlyScriptSig(index));
+            }
+            if input.script_sig.has_non_minimal_push() {
+                warnings.push(Warning::NonMinimalPush(index));
+            }
+        }
+        warnings
+    }
+
+    pub fn overhead_weight(&self) -> u64 {
+        let input_count = CompactSize::new(self.inputs.len() as u64);
+        (4 + input_count.to_bytes().len() as u64 + 4) * 4
+    }
+
+    /// Reads exactly one transaction, failing with `InvalidFormat` before any
+    /// field would take the total past `max_bytes`.
+    pub fn read_from_limited<R: Read>(r: &mut R, max_bytes: usize) -> Result<Self, BitcoinError> {
+        let mut reader = LimitedReader {
+            reader: r,
+            remaining: max_bytes,
+        };
+        let version = reader.read_u32()?;
+        let input_count = reader.read_compact_size()?;
+        let mut inputs = Vec::new();
+        for _ in 0..input_count.value {
+            let (previous_output, _) = OutPoint::from_bytes(&reader.read_bytes(36)?)?;
+            let script_len = reader.read_compact_size()?;
+            let script_len =
+                usize::try_from(script_len.value).map_err(|_| BitcoinError::InvalidFormat)?;
+            let script_sig = Script::new(reader.read_bytes(script_len)?);
+            let sequence = reader.read_u32()?;
+            inputs.push(TransactionInput::new(previous_output, script_sig, sequence));
+        }
+        let lock_time = reader.read_u32()?;
+        Ok(BitcoinTransaction::new(version, inputs, lock_time))
+    }
+
+    pub fn from_bytes_at(bytes: &[u8], offset: usize) -> Result<(Self, usize), BitcoinError> {
+        let remaining = bytes.get(offset..).ok_or(BitcoinError::InsufficientBytes)?;
+        let (tx, consumed) = BitcoinTransaction::from_bytes(remaining)?;
+        Ok((tx, offset + consumed))
+    }
+
+    /// `bucket_edges` must be ascending; bucket `i` covers `[edges[i - 1], edges[i])`,
+    /// so rates below the first edge land in bucket 0.
+    pub fn feerate_bucket(&self, fee: u64, bucket_edges: &[f64]) -> usize {
+        let feerate = fee as f64 / self.vsize() as f64;
+        bucket_edges.partition_point(|edge| *edge <= feerate)
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    pub fn from_hex(hex_str: &str) -> Result<Self, BitcoinError> {
+        let hex_str: String = hex_str
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect();
+        let bytes = hex::decode(hex_str).map_err(|_| BitcoinError::InvalidFormat)?;
+        let (tx, consumed) = BitcoinTransaction::from_bytes(&bytes)?;
+        if consumed != bytes.len() {
+            return Err(BitcoinError::InvalidFormat);
+        }
+        Ok(tx)
+    }
+
+    pub fn verify_hex_roundtrip(&self) -> bool {
+        BitcoinTransaction::from_hex(&self.to_hex()).as_ref() == Ok(self)
+    }
+
+    pub fn sequence_values(&self) -> Vec<u32> {
+        self.inputs.iter().map(|input| input.sequence).collect()
+    }
+
+    pub fn uses_uniform_sequences(&self) -> bool {
+        self.inputs
+            .windows(2)
+            .all(|pair| pair[0].sequence == pair[1].sequence)
+    }
+
+    pub fn non_final_inputs(&self) -> Vec<usize> {
+        self.inputs
+            .iter()
+            .enumerate()
+            .filter(|(_, input)| input.sequence != 0xFFFFFFFF)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    pub fn fee_for_target<F: Fn(u16) -> f64>(&self, target_blocks: u16, estimator: F) -> u64 {
+        (self.vsize() as f64 * estimator(target_blocks)).ceil() as u64
+    }
+
+    /// Only inputs are reordered, as transactions here have no outputs.
+    pub fn canonical_sorted(&self) -> BitcoinTransaction {
+        let mut sorted = self.clone();
+        sorted.inputs.sort_by(bip69_input_cmp);
+        sorted
+    }
+
+    pub fn input_offset(&self, index: usize) -> Result<usize, BitcoinError> {
+        if index >= self.inputs.len() {
+            return Err(BitcoinError::InvalidInputIndex);
+        }
+        let input_count = CompactSize::new(self.inputs.len() as u64);
+        let preceding: usize = self.inputs[..index]
+            .iter()
+            .map(TransactionInput::serialized_size)
+            .sum();
+        Ok(4 + input_count.to_bytes().len() + preceding)
+    }
+
+    /// Calls `progress` with the number of bytes written after each section.
+    pub fn write_to_with_progress<W: Write, F: FnMut(usize)>(
+        &self,
+        w: &mut W,
+        mut progress: F,
+    ) -> io::Result<()> {
+        let mut header = self.version.to_le_bytes().to_vec();
+        header.extend(CompactSize::new(self.inputs.len() as u64).to_bytes());
+        w.write_all(&header)?;
+        progress(header.len());
+        for input in &self.inputs {
+            let bytes = input.to_bytes();
+            w.write_all(&bytes)?;
+            progress(bytes.len());
+        }
+        w.write_all(&self.lock_time.to_le_bytes())?;
+        progress(4);
+        Ok(())
+    }
+
+    /// Heuristic signals only; output-based signals are not available since
+    /// transactions here have no outputs.
+    pub fn wallet_fingerprint(&self) -> WalletHints {
+        let signatures = self.signature_pushes();
+        WalletHints {
+            height_lock_time: self.lock_time > 0 && self.lock_time < LOCK_TIME_THRESHOLD,
+            bip69_inputs: self.inputs.len() > 1
+                && self
+                    .inputs
+                    .windows(2)
+                    .all(|pair| bip69_input_cmp(&pair[0], &pair[1]) != Ordering::Greater),
+            signals_rbf: self.signals_rbf(),
+            low_r_signatures: !signatures.is_empty()
+                && signatures
+                    .iter()
+                    .all(|(_, signature)| signature.len() <= 71),
+            uniform_sequences: self.uses_uniform_sequences(),
+        }
+    }
+
+    pub fn vsize_without_input(&self, index: usize) -> Result<u64, BitcoinError> {
+        if index >= self.inputs.len() {
+            return Err(BitcoinError::InvalidInputIndex);
+        }
+        let input_count = CompactSize::new(self.inputs.len() as u64 - 1);
+        let inputs_size: usize = self
+            .inputs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, input)| input.serialized_size())
+            .sum();
+        Ok((4 + input_count.to_bytes().len() + inputs_size + 4) as u64)
+    }
+
+    pub fn to_bytes_with_options(&self, opts: SerializeOptions) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(&self.version.to_le_bytes());
+        bytes.extend(opts.encode_count(self.inputs.len() as u64));
+        for input in &self.inputs {
+            bytes.extend(input.previous_output.to_bytes());
+            bytes.extend(opts.encode_count(input.script_sig.len() as u64));
+            bytes.extend(&input.script_sig.bytes);
+            bytes.extend(&input.sequence.to_le_bytes());
+        }
+        bytes.extend(&self.lock_time.to_le_bytes());
+        bytes
+    }
+
+    pub fn referenced_txids(&self) -> Vec<Txid> {
+        let mut txids: Vec<Txid> = Vec::new();
+        for outpoint in self.spent_outpoints() {
+            if !txids.contains(&outpoint.txid) {
+                txids.push(outpoint.txid.clone());
+            }
+        }
+        txids
+    }
+
+    pub fn is_truc(&self) -> bool {
+        self.version == 3
+    }
+
+    /// Checks the TRUC limits decidable from the transaction alone. The
+    /// single-unconfirmed-parent and child-size rules need mempool context and
+    /// are left to the caller. Non-TRUC transactions always pass.
+    pub fn validate_truc_limits(&self) -> Result<(), BitcoinError> {
+        if self.is_truc() && self.vsize() > TRUC_MAX_VSIZE {
+            return Err(BitcoinError::InvalidFormat);
+        }
+        Ok(())
+    }
+
+    /// Returns `(present, required)` per input; inputs without an entry in
+    /// `required` are reported as requiring none.
+    pub fn signing_status(&self, required: &[usize]) -> Vec<(usize, usize)> {
+        self.inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| {
+                (
+                    input.script_sig.count_signatures(),
+                    required.get(index).copied().unwrap_or(0),
+                )
+            })
+            .collect()
+    }
+
+    pub fn inputs_bytes(&self) -> Vec<u8> {
+        BitcoinSerialize::to_bytes(&self.inputs)
+    }
+
+    /// Minimum fee for a replacement of `my_vsize` vbytes under BIP125: it must pay
+    /// the competitor's fee plus incremental relay fee for its own size, and beat
+    /// the competitor's feerate. Returns `None` if `competitor_vsize` is zero or the
+    /// fee does not fit in a `u64`.
+    pub fn fee_to_beat(
+        &self,
+        my_vsize: u64,
+        competitor_fee: u64,
+        competitor_vsize: u64,
+        incremental_relay_feerate: f64,
+    ) -> Option<u64> {
+        if competitor_vsize == 0 {
+            return None;
+        }
+        let incremental_fee = (my_vsize as f64 * incremental_relay_feerate).ceil() as u128;
+        let by_absolute_fee = (competitor_fee as u128).checked_add(incremental_fee)?;
+        let by_feerate = competitor_fee as u128 * my_vsize as u128 / competitor_vsize as u128 + 1;
+        u64::try_from(by_absolute_fee.max(by_feerate)).ok()
+    }
+
+    /// Consensus finality check for inclusion in a block at `height`, comparing
+    /// time-based lock times against `median_time_past` (BIP113). On failure the
+    /// error carries the lock_time and the first input keeping it enforced.
+    pub fn check_locktime(&self, height: u32, median_time_past: u32) -> Result<(), BitcoinError> {
+        if self.lock_time == 0 {
+            return Ok(());
+        }
+        let cutoff = if self.lock_time < LOCK_TIME_THRESHOLD {
+            height
+        } else {
+            median_time_past
+        };
+        if self.lock_time < cutoff {
+            return Ok(());
+        }
+        match self.non_final_inputs().first() {
+            Some(&input_index) => Err(BitcoinError::LockTimeNotSatisfied {
+                lock_time: self.lock_time,
+                input_index,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// BIP68 relative lock check for inclusion in a block at `current_height`
+    /// whose previous block has median time past `current_mtp`. The parent
+    /// slices are aligned with the inputs. They give each spent output's
+    /// confirmation height and the median time past of the block *before* the one
+    /// that confirmed it (Core's `GetAncestor(coinHeight - 1)`). Transactions
+    /// below version 2 are exempt.
+    pub fn check_sequence_locks(
+        &self,
+        parent_heights: &[u32],
+        parent_mtps: &[u32],
+        current_height: u32,
+        current_mtp: u32,
+    ) -> Result<(), BitcoinError> {
+        if parent_heights.len() != self.inputs.len() || parent_mtps.len() != self.inputs.len() {
+            return Err(BitcoinError::InvalidFormat);
+        }
+        if self.version < 2 {
+            return Ok(());
+        }
+        for (input_index, input) in self.inputs.iter().enumerate() {
+            let (min_height, min_time) =
+                input.spendable_at(parent_heights[input_index], parent_mtps[input_index]);
+            if current_height < min_height || current_mtp < min_time {
+                return Err(BitcoinError::SequenceLockNotSatisfied { input_index });
+            }
+        }
+        Ok(())
+    }
+}
+
+struct LimitedReader<'a, R: Read> {
+    reader: &'a mut R,
+    remaining: usize,
+}
+
+impl<R: Read> LimitedReader<'_, R> {
+    fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>, BitcoinError> {
+        if len > self.remaining {
+            return Err(BitcoinError::InvalidFormat);
+        }
+        self.remaining -= len;
+        let mut bytes = vec![0u8; len];
+        self.reader
+            .read_exact(&mut bytes)
+            .map_err(|_| BitcoinError::InsufficientBytes)?;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, BitcoinError> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn read_compact_size(&mut self) -> Result<CompactSize, BitcoinError> {
+        let mut bytes = self.read_bytes(1)?;
+        let extra = match bytes[0] {
+            0xFD => 2,
+            0xFE => 4,
+            0xFF => 8,
+            _ => 0,
+        };
+        bytes.extend(self.read_bytes(extra)?);
+        Ok(CompactSize::from_bytes(&bytes)?.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct SerializeOptions {
+    /// Encode every count and script length one CompactSize width wider than needed.
+    pub non_minimal_counts: bool,
+}
+
+impl SerializeOptions {
+    fn encode_count(&self, value: u64) -> Vec<u8> {
+        if !self.non_minimal_counts {
+            return CompactSize::new(value).to_bytes();
+        }
+        let (prefix, width) = if value <= 0xFC {
+            (0xFD, 2)
+        } else if value <= 0xFFFF {
+            (0xFE, 4)
+        } else {
+            (0xFF, 8)
+        };
+        let mut bytes = vec![prefix];
+        bytes.extend(&value.to_le_bytes()[..width]);
+        bytes
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct WalletHints {
+    /// lock_time is set to a block height, as anti-fee-sniping wallets do.
+    pub height_lock_time: bool,
+    /// More than one input, in BIP69 order.
+    pub bip69_inputs: bool,
+    pub signals_rbf: bool,
+    /// Every signature is at most 71 bytes, suggesting low-R grinding.
+    pub low_r_signatures: bool,
+    pub uniform_sequences: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Warning {
+    NonStandardVersion(u32),
+    SignalsRbf,
+    NonPushOnlyScriptSig(usize),
+    NonMinimalPush(usize),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::NonStandardVersion(version) => {
+                write!(f, "Non-standard transaction version {}", version)
+            }
+            Warning::SignalsRbf => write!(f, "Transaction signals replace-by-fee"),
+            Warning::NonPushOnlyScriptSig(index) => {
+                write!(f, "Input {} scriptSig is not push-only", index)
+            }
+            Warning::NonMinimalPush(index) => {
+                write!(f, "Input {} scriptSig uses a non-minimal push", index)
+            }
+        }
+    }
 }
 
 impl fmt::Display for BitcoinTransaction {
@@ -278,3 +1208,247 @@ impl fmt::Display for BitcoinTransaction {
         Ok(())
     }
 }
+
+pub fn missing_prevouts<V>(tx: &BitcoinTransaction, utxos: &HashMap<OutPoint, V>) -> Vec<OutPoint> {
+    tx.spent_outpoints()
+        .filter(|outpoint| !utxos.contains_key(*outpoint))
+        .cloned()
+        .collect()
+}
+
+/// Reports every pair of transactions spending the same outpoint, as
+/// `(earlier, later, outpoint)`. An outpoint spent twice within one transaction
+/// is not a conflict between transactions and is not reported.
+pub fn find_conflicts(txs: &[BitcoinTransaction]) -> Vec<(usize, usize, OutPoint)> {
+    let mut spenders: HashMap<&OutPoint, Vec<usize>> = HashMap::new();
+    let mut conflicts = Vec::new();
+    for (index, tx) in txs.iter().enumerate() {
+        for outpoint in tx.spent_outpoints() {
+            let earlier = spenders.entry(outpoint).or_default();
+            if earlier.last() == Some(&index) {
+                continue;
+            }
+            for &spender in earlier.iter() {
+                conflicts.push((spender, index, outpoint.clone()));
+            }
+            earlier.push(index);
+        }
+    }
+    conflicts
+}
+
+pub fn block_subsidy(height: u32) -> u64 {
+    let halvings = height / 210_000;
+    if halvings >= 64 {
+        return 0;
+    }
+    (50 * 100_000_000u64) >> halvings
+}
+
+pub fn compute_change(total_input: u64, payment: u64, fee: u64) -> Result<u64, BitcoinError> {
+    payment
+        .checked_add(fee)
+        .and_then(|spent| total_input.checked_sub(spent))
+        .ok_or(BitcoinError::InsufficientFunds)
+}
+
+/// Greedy largest-first selection where each input must also pay for its own weight.
+pub fn select_inputs(
+    utxos: &[(OutPoint, u64)],
+    target: u64,
+    feerate: f64,
+    input_weight: u64,
+) -> Option<Vec<OutPoint>> {
+    let input_cost = (input_weight as f64 / 4.0 * feerate).ceil() as u64;
+    let mut candidates: Vec<_> = utxos
+        .iter()
+        .filter(|(_, value)| *value > input_cost)
+        .collect();
+    candidates.sort_by_key(|(_, value)| Reverse(*value));
+
+    let mut selected = Vec::new();
+    let mut effective_total = 0u64;
+    for (outpoint, value) in candidates {
+        if effective_total >= target {
+            break;
+        }
+        selected.push(outpoint.clone());
+        effective_total += value - input_cost;
+    }
+    (effective_total >= target).then_some(selected)
+}
+
+/// Typical witness weight to spend an output of `script_type`, assuming one
+/// 72-byte signature (with sighash flag) and a 33-byte compressed key for P2WPKH,
+/// and a 64-byte SIGHASH_DEFAULT signature for P2TR key-path spends.
+/// Non-witness types need no witness; P2WSH depends on the witness script and
+/// is returned as 0.
+pub fn expected_witness_weight(script_type: ScriptType) -> u64 {
+    match script_type {
+        // stack count + sig push + pubkey push
+        ScriptType::P2WPKH => 1 + (1 + 72) + (1 + 33),
+        // stack count + sig push
+        ScriptType::P2TR => 1 + (1 + 64),
+        // empty stack
+        ScriptType::P2A => 1,
+        ScriptType::P2PK
+        | ScriptType::P2PKH
+        | ScriptType::P2SH
+        | ScriptType::P2WSH
+        | ScriptType::OpReturn
+        | ScriptType::NonStandard => 0,
+    }
+}
+
+pub struct TransactionIter<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Iterator for TransactionIter<'_> {
+    type Item = Result<(BitcoinTransaction, usize), BitcoinError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.bytes.len() {
+            return None;
+        }
+        let start = self.offset;
+        match BitcoinTransaction::from_bytes_at(self.bytes, start) {
+            Ok((tx, next_offset)) => {
+                self.offset = next_offset;
+                Some(Ok((tx, start)))
+            }
+            Err(e) => {
+                self.offset = self.bytes.len();
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Parses back-to-back transactions, yielding each with its starting offset.
+/// Iteration stops after the first error.
+pub fn iter_transactions(bytes: &[u8]) -> TransactionIter<'_> {
+    TransactionIter { bytes, offset: 0 }
+}
+
+pub fn parse_outpoint_list(bytes: &[u8]) -> Result<(Vec<OutPoint>, usize), BitcoinError> {
+    Vec::<OutPoint>::from_bytes(bytes)
+}
+
+pub trait BitcoinSerialize: Sized {
+    fn to_bytes(&self) -> Vec<u8>;
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError>;
+}
+
+impl BitcoinSerialize for CompactSize {
+    fn to_bytes(&self) -> Vec<u8> {
+        CompactSize::to_bytes(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
+        CompactSize::from_bytes(bytes)
+    }
+}
+
+impl BitcoinSerialize for OutPoint {
+    fn to_bytes(&self) -> Vec<u8> {
+        OutPoint::to_bytes(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
+        OutPoint::from_bytes(bytes)
+    }
+}
+
+impl BitcoinSerialize for Script {
+    fn to_bytes(&self) -> Vec<u8> {
+        Script::to_bytes(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
+        Script::from_bytes(bytes)
+    }
+}
+
+impl BitcoinSerialize for TransactionInput {
+    fn to_bytes(&self) -> Vec<u8> {
+        TransactionInput::to_bytes(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
+        TransactionInput::from_bytes(bytes)
+    }
+}
+
+impl BitcoinSerialize for BitcoinTransaction {
+    fn to_bytes(&self) -> Vec<u8> {
+        BitcoinTransaction::to_bytes(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
+        BitcoinTransaction::from_bytes(bytes)
+    }
+}
+
+impl<T: BitcoinSerialize> BitcoinSerialize for Vec<T> {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = CompactSize::new(self.len() as u64).to_bytes();
+        for item in self {
+            bytes.extend(item.to_bytes());
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
+        let (count, mut consumed) = CompactSize::from_bytes(bytes)?;
+        let mut items = Vec::new();
+        for _ in 0..count.value {
+            let (item, item_consumed) = T::from_bytes(&bytes[consumed..])?;
+            items.push(item);
+            consumed += item_consumed;
+        }
+        Ok((items, consumed))
+    }
+}
+
+#[cfg(feature = "elements")]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ConfidentialAmount {
+    Explicit(u64),
+    /// Pedersen commitment, including its 0x08/0x09 prefix byte.
+    Confidential([u8; 33]),
+}
+
+#[cfg(feature = "elements")]
+impl ConfidentialAmount {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            ConfidentialAmount::Explicit(value) => {
+                let mut bytes = vec![0x01];
+                bytes.extend(&value.to_be_bytes());
+                bytes
+            }
+            ConfidentialAmount::Confidential(commitment) => commitment.to_vec(),
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), BitcoinError> {
+        match bytes.first() {
+            None => Err(BitcoinError::InsufficientBytes),
+            Some(0x01) => {
+                let value = bytes.get(1..9).ok_or(BitcoinError::InsufficientBytes)?;
+                let value = u64::from_be_bytes(value.try_into().unwrap());
+                Ok((ConfidentialAmount::Explicit(value), 9))
+            }
+            Some(0x08 | 0x09) => {
+                let commitment = bytes.get(..33).ok_or(BitcoinError::InsufficientBytes)?;
+                Ok((
+                    ConfidentialAmount::Confidential(commitment.try_into().unwrap()),
+                    33,
+                ))
+            }
+            Some(_) => Err(BitcoinError::InvalidFormat),
+        }
+    }
+}
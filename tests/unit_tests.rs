@@ -1,4 +1,5 @@
 use rust_week_3_exercises::*;
+use std::collections::HashMap;
 
 #[cfg(test)]
 mod tests {
@@ -10,6 +11,28 @@ mod tests {
         txid
     }
 
+    fn dummy_signature(len: usize) -> Vec<u8> {
+        // 0x30 <len> 0x02 <r_len> <r> 0x02 0x20 <s> <sighash>
+        let r_len = len - 39;
+        let mut sig = vec![0x30, (len - 3) as u8, 0x02, r_len as u8];
+        sig.extend(vec![0x11; r_len]);
+        sig.extend([0x02, 0x20]);
+        sig.extend([0x22; 32]);
+        sig.push(0x01);
+        sig
+    }
+
+    fn dummy_multisig_redeem_script() -> Script {
+        // OP_2 <key> <key> <key> OP_3 OP_CHECKMULTISIG
+        let mut bytes = vec![0x52];
+        for key in [0x02, 0x03, 0x04] {
+            bytes.push(0x21);
+            bytes.extend([key; 33]);
+        }
+        bytes.extend([0x53, 0xAE]);
+        Script::new(bytes)
+    }
+
     #[test]
     fn test_compact_size_serialization() {
         let tests = vec![
@@ -110,4 +133,1319 @@ mod tests {
         assert!(output.contains("Lock Time: 0"));
         assert!(output.contains("Previous Output Vout: 7"));
     }
+
+    #[test]
+    fn test_input_timelocks() {
+        let inputs = vec![
+            TransactionInput::new(OutPoint::new(dummy_txid(1), 0), Script::new(vec![]), 10),
+            TransactionInput::new(
+                OutPoint::new(dummy_txid(2), 1),
+                Script::new(vec![]),
+                (1 << 22) | 3,
+            ),
+            TransactionInput::new(
+                OutPoint::new(dummy_txid(3), 2),
+                Script::new(vec![]),
+                0xFFFFFFFF,
+            ),
+        ];
+        let tx = BitcoinTransaction::new(2, inputs, 0);
+        let timelocks: Vec<_> = tx.input_timelocks().collect();
+        assert_eq!(
+            timelocks,
+            vec![
+                (
+                    OutPoint::new(dummy_txid(1), 0),
+                    Some(RelativeLockTime::Blocks(10))
+                ),
+                (
+                    OutPoint::new(dummy_txid(2), 1),
+                    Some(RelativeLockTime::Time(3))
+                ),
+                (OutPoint::new(dummy_txid(3), 2), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_script_type() {
+        let p2a = Script::new(vec![0x51, 0x02, 0x4E, 0x73]);
+        assert_eq!(p2a.script_type(), ScriptType::P2A);
+
+        let mut p2wpkh = vec![0x00, 0x14];
+        p2wpkh.extend([0x11; 20]);
+        assert_eq!(Script::new(p2wpkh).script_type(), ScriptType::P2WPKH);
+
+        let mut p2tr = vec![0x51, 0x20];
+        p2tr.extend([0x22; 32]);
+        assert_eq!(Script::new(p2tr).script_type(), ScriptType::P2TR);
+
+        let other_v1 = Script::new(vec![0x51, 0x02, 0x00, 0x00]);
+        assert_eq!(other_v1.script_type(), ScriptType::NonStandard);
+    }
+
+    #[test]
+    fn test_empty_transaction_roundtrip() {
+        let tx = BitcoinTransaction::new(1, vec![], 0);
+        let bytes = tx.to_bytes();
+        assert_eq!(
+            bytes,
+            vec![0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+        let (parsed, consumed) = BitcoinTransaction::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed, tx);
+        assert_eq!(consumed, 9);
+    }
+
+    #[test]
+    fn test_exceeds_standard_weight() {
+        let small = BitcoinTransaction::new(
+            1,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x01, 0x02]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        assert!(!small.exceeds_standard_weight());
+
+        let large = BitcoinTransaction::new(
+            1,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x00; 100_000]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        assert!(large.weight() > MAX_STANDARD_TX_WEIGHT);
+        assert!(large.exceeds_standard_weight());
+    }
+
+    #[test]
+    fn test_taproot_output_key() {
+        let key = [0x5A; 32];
+        let mut p2tr = vec![0x51, 0x20];
+        p2tr.extend(key);
+        assert_eq!(Script::new(p2tr).taproot_output_key(), Some(key));
+
+        let mut p2wsh = vec![0x00, 0x20];
+        p2wsh.extend(key);
+        assert_eq!(Script::new(p2wsh).taproot_output_key(), None);
+    }
+
+    #[test]
+    fn test_input_incremental_weight() {
+        let mut tx = BitcoinTransaction::new(
+            2,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x01, 0x02]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        let before = tx.weight();
+        let input = TransactionInput::new(
+            OutPoint::new(dummy_txid(2), 1),
+            Script::new(vec![0xAB; 107]),
+            0xFFFFFFFD,
+        );
+        tx.inputs.push(input.clone());
+        assert_eq!(tx.weight() - before, input.incremental_weight());
+    }
+
+    #[test]
+    fn test_missing_prevouts() {
+        let present = OutPoint::new(dummy_txid(1), 0);
+        let missing = OutPoint::new(dummy_txid(2), 1);
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![
+                TransactionInput::new(present.clone(), Script::new(vec![]), 0xFFFFFFFF),
+                TransactionInput::new(missing.clone(), Script::new(vec![]), 0xFFFFFFFF),
+            ],
+            0,
+        );
+        let mut utxos = HashMap::new();
+        utxos.insert(present, 50_000u64);
+        assert_eq!(missing_prevouts(&tx, &utxos), vec![missing]);
+    }
+
+    #[test]
+    fn test_coinbase_scriptsig() {
+        let coinbase_input = TransactionInput::new(
+            OutPoint::new([0u8; 32], 0xFFFFFFFF),
+            Script::new(vec![0x03, 0x40, 0x0D, 0x03, 0x00, 0x00]),
+            0xFFFFFFFF,
+        );
+        let mut tx = BitcoinTransaction::new(1, vec![coinbase_input], 0);
+        assert!(tx.is_coinbase());
+        assert_eq!(
+            tx.coinbase_scriptsig(),
+            Some(&[0x03, 0x40, 0x0D, 0x03, 0x00, 0x00][..])
+        );
+
+        tx.set_coinbase_scriptsig(vec![0x03, 0x40, 0x0D, 0x03, 0xFF, 0xFF]);
+        assert_eq!(
+            tx.coinbase_scriptsig(),
+            Some(&[0x03, 0x40, 0x0D, 0x03, 0xFF, 0xFF][..])
+        );
+
+        let regular = BitcoinTransaction::new(
+            1,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x01]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        assert_eq!(regular.coinbase_scriptsig(), None);
+    }
+
+    #[test]
+    fn test_block_subsidy() {
+        assert_eq!(block_subsidy(0), 5_000_000_000);
+        assert_eq!(block_subsidy(209_999), 5_000_000_000);
+        assert_eq!(block_subsidy(210_000), 2_500_000_000);
+        assert_eq!(block_subsidy(64 * 210_000), 0);
+    }
+
+    #[test]
+    fn test_nested_witness_program() {
+        let mut redeem_script = vec![0x00, 0x14];
+        redeem_script.extend([0x33; 20]);
+        let mut script_sig = vec![0x16];
+        script_sig.extend(&redeem_script);
+        let input = TransactionInput::new(
+            OutPoint::new(dummy_txid(1), 0),
+            Script::new(script_sig),
+            0xFFFFFFFF,
+        );
+        assert!(input.is_p2sh_segwit());
+        assert_eq!(input.nested_witness_program(), Some(&redeem_script[..]));
+
+        let p2pkh_input = TransactionInput::new(
+            OutPoint::new(dummy_txid(1), 0),
+            Script::new(vec![0x02, 0x30, 0x44, 0x02, 0x02, 0x03]),
+            0xFFFFFFFF,
+        );
+        assert!(!p2pkh_input.is_p2sh_segwit());
+    }
+
+    #[test]
+    fn test_p2sh_multisig_scriptsig() {
+        let redeem_script = dummy_multisig_redeem_script();
+        let signatures = vec![vec![0x30; 71], vec![0x31; 72]];
+
+        let script_sig = Script::new_p2sh_multisig_scriptsig(&signatures, &redeem_script);
+        let instructions: Vec<_> = script_sig.instructions().collect::<Result<_, _>>().unwrap();
+        assert_eq!(
+            instructions,
+            vec![
+                Instruction::PushBytes(&[]),
+                Instruction::PushBytes(&signatures[0]),
+                Instruction::PushBytes(&signatures[1]),
+                Instruction::PushBytes(&redeem_script),
+            ]
+        );
+        assert_eq!(script_sig[0], 0x00);
+        assert_eq!(&script_sig[1 + 72 + 73..][..2], &[0x4C, 105]);
+    }
+
+    #[test]
+    fn test_fee_overpayment() {
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x00; 50]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        assert_eq!(tx.vsize(), 100);
+        assert_eq!(tx.fee_overpayment(1_500, 10.0), 500);
+        assert_eq!(tx.fee_overpayment(800, 10.0), -200);
+        assert_eq!(tx.fee_overpayment(u64::MAX, 10.0), i64::MAX);
+        assert_eq!(tx.fee_overpayment(0, f64::MAX), i64::MIN);
+    }
+
+    #[test]
+    fn test_signature_pushes() {
+        let signature = dummy_signature(71);
+        let mut script_sig = vec![signature.len() as u8];
+        script_sig.extend(&signature);
+        script_sig.push(0x21);
+        script_sig.extend([0x02; 33]);
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(1), 0),
+                    Script::new(vec![0x01, 0x02]),
+                    0xFFFFFFFF,
+                ),
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(2), 0),
+                    Script::new(script_sig),
+                    0xFFFFFFFF,
+                ),
+            ],
+            0,
+        );
+        assert_eq!(tx.signature_pushes(), vec![(1, signature)]);
+    }
+
+    #[test]
+    fn test_script_is_push_only() {
+        let push_only = Script::new(vec![0x00, 0x02, 0xAA, 0xBB, 0x51, 0x4C, 0x01, 0xCC]);
+        assert!(push_only.is_push_only());
+
+        let with_dup = Script::new(vec![0x02, 0xAA, 0xBB, 0x76]);
+        assert!(!with_dup.is_push_only());
+
+        // OP_RESERVED counts as a push, matching Core's IsPushOnly.
+        let with_reserved = Script::new(vec![0x50, 0x51]);
+        assert!(with_reserved.is_push_only());
+
+        let truncated = Script::new(vec![0x05, 0xAA]);
+        assert!(!truncated.is_push_only());
+    }
+
+    #[test]
+    fn test_to_bytes_with_input_scripts_blanked() {
+        let inputs: Vec<_> = (0..3)
+            .map(|i| {
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(i), i as u32),
+                    Script::new(vec![0xA0 + i; 3]),
+                    0xFFFFFFFF,
+                )
+            })
+            .collect();
+        let tx = BitcoinTransaction::new(1, inputs, 0);
+
+        let bytes = tx.to_bytes_with_input_scripts_blanked(Some(1));
+        let (parsed, consumed) = BitcoinTransaction::from_bytes(&bytes).unwrap();
+        assert_eq!(consumed, bytes.len());
+        assert!(parsed.inputs[0].script_sig.is_empty());
+        assert_eq!(parsed.inputs[1].script_sig, tx.inputs[1].script_sig);
+        assert!(parsed.inputs[2].script_sig.is_empty());
+
+        let bytes = tx.to_bytes_with_input_scripts_blanked(None);
+        let (parsed, _) = BitcoinTransaction::from_bytes(&bytes).unwrap();
+        assert!(
+            parsed
+                .inputs
+                .iter()
+                .all(|input| input.script_sig.is_empty())
+        );
+        assert_eq!(
+            parsed.inputs[2].previous_output,
+            tx.inputs[2].previous_output
+        );
+    }
+
+    #[test]
+    fn test_lock_time_threshold() {
+        assert_eq!(
+            LockTime::from_height(499_999_999),
+            Ok(LockTime::Blocks(499_999_999))
+        );
+        assert_eq!(
+            LockTime::from_height(500_000_000),
+            Err(BitcoinError::InvalidFormat)
+        );
+        assert_eq!(
+            LockTime::from_time(500_000_000),
+            Ok(LockTime::Seconds(500_000_000))
+        );
+        assert_eq!(
+            LockTime::from_time(499_999_999),
+            Err(BitcoinError::InvalidFormat)
+        );
+
+        let mut tx = BitcoinTransaction::new(2, vec![], 0);
+        tx.set_lock_time(LockTime::from_height(840_000).unwrap());
+        assert_eq!(tx.lock_time, 840_000);
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0xAB, 0xCD]),
+                0xFFFFFFFE,
+            )],
+            100,
+        );
+        let dump = tx.hex_dump();
+        assert!(dump.contains("version: 02000000"));
+        assert!(dump.contains("vin_count: 01"));
+        assert!(dump.contains("vin[0].vout: 00000000"));
+        assert!(dump.contains("vin[0].script_len: 02"));
+        assert!(dump.contains("vin[0].script_sig: abcd"));
+        assert!(dump.contains("vin[0].sequence: feffffff"));
+        assert!(dump.ends_with("lock_time: 64000000"));
+
+        let concatenated: String = dump
+            .lines()
+            .map(|line| line.split(": ").nth(1).unwrap())
+            .collect();
+        assert_eq!(concatenated, hex::encode(tx.to_bytes()));
+    }
+
+    #[test]
+    fn test_compact_size_is_canonical() {
+        assert!(CompactSize::is_canonical(&[0xFC]));
+        assert!(!CompactSize::is_canonical(&[0xFD, 0xFC, 0x00]));
+
+        assert!(CompactSize::is_canonical(&[0xFD, 0xFD, 0x00]));
+        assert!(!CompactSize::is_canonical(&[0xFE, 0xFD, 0x00, 0x00, 0x00]));
+        assert!(!CompactSize::is_canonical(&[
+            0xFF, 0xFD, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        ]));
+
+        assert!(!CompactSize::is_canonical(&[0xFD, 0xFD]));
+        assert!(!CompactSize::is_canonical(&[]));
+    }
+
+    #[test]
+    fn test_bitcoin_tx_from_parts() {
+        let tx = BitcoinTransaction::from_parts(
+            [0x02, 0x00, 0x00, 0x00],
+            vec![],
+            [0x40, 0x0D, 0x03, 0x00],
+        );
+        assert_eq!(tx.version, 2);
+        assert_eq!(tx.lock_time, 200_000);
+        assert_eq!(&tx.to_bytes()[..4], &[0x02, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_bitcoin_tx_fingerprint() {
+        let input = TransactionInput::new(
+            OutPoint::new(dummy_txid(1), 0),
+            Script::new(vec![0x01, 0x02]),
+            0xFFFFFFFF,
+        );
+        let tx = BitcoinTransaction::new(2, vec![input.clone()], 0);
+        let same = BitcoinTransaction::new(2, vec![input.clone()], 0);
+        let different = BitcoinTransaction::new(2, vec![input], 1);
+        assert_eq!(tx.fingerprint(), same.fingerprint());
+        assert_ne!(tx.fingerprint(), different.fingerprint());
+    }
+
+    #[test]
+    fn test_vec_outpoint_roundtrip() {
+        let outpoints = vec![
+            OutPoint::new(dummy_txid(1), 0),
+            OutPoint::new(dummy_txid(2), 5),
+        ];
+        let bytes = BitcoinSerialize::to_bytes(&outpoints);
+        assert_eq!(bytes.len(), 1 + 2 * 36);
+        assert_eq!(bytes[0], 0x02);
+        let (parsed, consumed) = Vec::<OutPoint>::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed, outpoints);
+        assert_eq!(consumed, bytes.len());
+
+        assert_eq!(
+            Vec::<OutPoint>::from_bytes(&bytes[..40]),
+            Err(BitcoinError::InsufficientBytes)
+        );
+    }
+
+    #[test]
+    fn test_prevouts_and_sequences_bytes() {
+        let first = OutPoint::new(dummy_txid(1), 0);
+        let second = OutPoint::new(dummy_txid(2), 3);
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![
+                TransactionInput::new(first.clone(), Script::new(vec![0x01]), 0xFFFFFFFF),
+                TransactionInput::new(second.clone(), Script::new(vec![]), 0x00000010),
+            ],
+            0,
+        );
+
+        let mut expected = first.to_bytes();
+        expected.extend(second.to_bytes());
+        assert_eq!(tx.prevouts_bytes(), expected);
+
+        assert_eq!(
+            tx.sequences_bytes(),
+            vec![0xFF, 0xFF, 0xFF, 0xFF, 0x10, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_input_spendable_at() {
+        let outpoint = OutPoint::new(dummy_txid(1), 0);
+        let block_locked = TransactionInput::new(outpoint.clone(), Script::new(vec![]), 144);
+        assert_eq!(
+            block_locked.spendable_at(800_000, 1_700_000_000),
+            (800_144, 0)
+        );
+
+        let time_locked =
+            TransactionInput::new(outpoint.clone(), Script::new(vec![]), (1 << 22) | 10);
+        assert_eq!(
+            time_locked.spendable_at(800_000, 1_700_000_000),
+            (0, 1_700_005_120)
+        );
+
+        let disabled = TransactionInput::new(outpoint, Script::new(vec![]), 0xFFFFFFFF);
+        assert_eq!(disabled.spendable_at(800_000, 1_700_000_000), (0, 0));
+    }
+
+    #[test]
+    fn test_compute_change() {
+        assert_eq!(compute_change(100_000, 60_000, 1_000), Ok(39_000));
+        assert_eq!(compute_change(61_000, 60_000, 1_000), Ok(0));
+        assert_eq!(
+            compute_change(60_500, 60_000, 1_000),
+            Err(BitcoinError::InsufficientFunds)
+        );
+        assert_eq!(
+            compute_change(u64::MAX, u64::MAX, 1),
+            Err(BitcoinError::InsufficientFunds)
+        );
+    }
+
+    #[test]
+    fn test_bitcoin_tx_lint() {
+        let tx = BitcoinTransaction::new(
+            7,
+            vec![
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(1), 0),
+                    Script::new(vec![0x4C, 0x02, 0xAA, 0xBB]),
+                    0xFFFFFFFD,
+                ),
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(2), 0),
+                    Script::new(vec![0x02, 0xAA, 0xBB, 0x76]),
+                    0xFFFFFFFF,
+                ),
+            ],
+            0,
+        );
+        let warnings = tx.lint();
+        assert_eq!(
+            warnings,
+            vec![
+                Warning::NonStandardVersion(7),
+                Warning::SignalsRbf,
+                Warning::NonMinimalPush(0),
+                Warning::NonPushOnlyScriptSig(1),
+            ]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "Non-standard transaction version 7"
+        );
+
+        let clean = BitcoinTransaction::new(
+            2,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x02, 0xAA, 0xBB]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        assert!(clean.lint().is_empty());
+    }
+
+    #[test]
+    fn test_overhead_weight() {
+        let input = TransactionInput::new(
+            OutPoint::new(dummy_txid(1), 0),
+            Script::new(vec![0x01, 0x02]),
+            0xFFFFFFFF,
+        );
+        let tx = BitcoinTransaction::new(2, vec![input.clone()], 0);
+        assert_eq!(tx.overhead_weight(), 36);
+        assert_eq!(
+            tx.overhead_weight() + input.incremental_weight(),
+            tx.weight()
+        );
+    }
+
+    #[test]
+    fn test_read_from_limited() {
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x01, 0x02]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        let bytes = tx.to_bytes();
+        let parsed = BitcoinTransaction::read_from_limited(&mut &bytes[..], bytes.len()).unwrap();
+        assert_eq!(parsed, tx);
+        assert_eq!(
+            BitcoinTransaction::read_from_limited(&mut &bytes[..], bytes.len() - 1),
+            Err(BitcoinError::InvalidFormat)
+        );
+
+        // One input whose scriptSig claims to be 4 GiB long.
+        let mut oversized = vec![0x02, 0x00, 0x00, 0x00, 0x01];
+        oversized.extend(OutPoint::new(dummy_txid(1), 0).to_bytes());
+        oversized.extend([0xFE, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(
+            BitcoinTransaction::read_from_limited(&mut &oversized[..], 100_000),
+            Err(BitcoinError::InvalidFormat)
+        );
+        assert_eq!(
+            BitcoinTransaction::read_from_limited(&mut &bytes[..3], 100_000),
+            Err(BitcoinError::InsufficientBytes)
+        );
+    }
+
+    #[test]
+    fn test_bitcoin_tx_from_bytes_at() {
+        let first = BitcoinTransaction::new(
+            1,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x01, 0x02]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        let second = BitcoinTransaction::new(2, vec![], 500);
+        let mut bytes = first.to_bytes();
+        bytes.extend(second.to_bytes());
+
+        let (parsed_first, offset) = BitcoinTransaction::from_bytes_at(&bytes, 0).unwrap();
+        assert_eq!(parsed_first, first);
+        let (parsed_second, offset) = BitcoinTransaction::from_bytes_at(&bytes, offset).unwrap();
+        assert_eq!(parsed_second, second);
+        assert_eq!(offset, bytes.len());
+        assert_eq!(
+            BitcoinTransaction::from_bytes_at(&bytes, bytes.len() + 1),
+            Err(BitcoinError::InsufficientBytes)
+        );
+
+        // The second transaction's only input is cut off inside its sequence.
+        let first_bytes = first.to_bytes();
+        let mut truncated = second.to_bytes();
+        truncated.extend(&first_bytes[..first_bytes.len() - 6]);
+        assert_eq!(
+            BitcoinTransaction::from_bytes_at(&truncated, 9),
+            Err(BitcoinError::InsufficientBytes)
+        );
+    }
+
+    #[test]
+    fn test_feerate_bucket() {
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x00; 50]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        let edges = [1.0, 5.0, 10.0, 50.0];
+        assert_eq!(tx.feerate_bucket(700, &edges), 2);
+        assert_eq!(tx.feerate_bucket(50, &edges), 0);
+        assert_eq!(tx.feerate_bucket(1_000, &edges), 3);
+        assert_eq!(tx.feerate_bucket(10_000, &edges), 4);
+    }
+
+    #[test]
+    fn test_p2pk_pubkey() {
+        let mut pubkey = vec![0x04];
+        pubkey.extend([0x67; 64]);
+        let mut script_bytes = vec![0x41];
+        script_bytes.extend(&pubkey);
+        script_bytes.push(0xAC);
+        let script = Script::new(script_bytes);
+        assert_eq!(script.p2pk_pubkey(), Some(&pubkey[..]));
+        assert_eq!(script.script_type(), ScriptType::P2PK);
+
+        let truncated = Script::new(vec![0x41, 0x04, 0xAC]);
+        assert_eq!(truncated.p2pk_pubkey(), None);
+    }
+
+    #[test]
+    fn test_input_serialized_size() {
+        for script_len in [0, 1, 252, 253, 300] {
+            let input = TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x00; script_len]),
+                0xFFFFFFFF,
+            );
+            assert_eq!(input.serialized_size(), input.to_bytes().len());
+        }
+    }
+
+    #[test]
+    fn test_bitcoin_tx_hex_roundtrip() {
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x01, 0x02]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        assert!(tx.verify_hex_roundtrip());
+        assert!(BitcoinTransaction::new(1, vec![], 0).verify_hex_roundtrip());
+
+        let mut corrupted = tx.clone();
+        corrupted.inputs[0].script_sig = Script::new(vec![0xFF]);
+        assert!(corrupted.verify_hex_roundtrip());
+        assert_ne!(
+            BitcoinTransaction::from_hex(&tx.to_hex()).unwrap(),
+            corrupted
+        );
+
+        let mut hex_str = tx.to_hex();
+        hex_str.push_str("00");
+        assert_eq!(
+            BitcoinTransaction::from_hex(&hex_str),
+            Err(BitcoinError::InvalidFormat)
+        );
+        assert_eq!(
+            BitcoinTransaction::from_hex("zz"),
+            Err(BitcoinError::InvalidFormat)
+        );
+
+        // Truncated two bytes into the input's sequence.
+        let hex_str = tx.to_hex();
+        let truncated = &hex_str[..hex_str.len() - 12];
+        assert_eq!(
+            BitcoinTransaction::from_hex(truncated),
+            Err(BitcoinError::InsufficientBytes)
+        );
+        assert_eq!(
+            BitcoinTransaction::from_hex(&format!("{}\n{}", &truncated[..20], &truncated[20..])),
+            Err(BitcoinError::InsufficientBytes)
+        );
+    }
+
+    #[test]
+    fn test_sequence_values() {
+        let input = |sequence| {
+            TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![]),
+                sequence,
+            )
+        };
+        let uniform = BitcoinTransaction::new(2, vec![input(0xFFFFFFFD), input(0xFFFFFFFD)], 0);
+        assert_eq!(uniform.sequence_values(), vec![0xFFFFFFFD, 0xFFFFFFFD]);
+        assert!(uniform.uses_uniform_sequences());
+
+        let mixed = BitcoinTransaction::new(2, vec![input(0xFFFFFFFD), input(0xFFFFFFFF)], 0);
+        assert_eq!(mixed.sequence_values(), vec![0xFFFFFFFD, 0xFFFFFFFF]);
+        assert!(!mixed.uses_uniform_sequences());
+    }
+
+    #[cfg(feature = "elements")]
+    #[test]
+    fn test_confidential_amount_roundtrip() {
+        let explicit_bytes = [0x01, 0x00, 0x00, 0x00, 0x00, 0x05, 0xF5, 0xE1, 0x00];
+        let (explicit, consumed) = ConfidentialAmount::from_bytes(&explicit_bytes).unwrap();
+        assert_eq!(explicit, ConfidentialAmount::Explicit(100_000_000));
+        assert_eq!(consumed, 9);
+        assert_eq!(explicit.to_bytes(), explicit_bytes);
+
+        let mut commitment = [0x42; 33];
+        commitment[0] = 0x09;
+        let (confidential, consumed) = ConfidentialAmount::from_bytes(&commitment).unwrap();
+        assert_eq!(confidential, ConfidentialAmount::Confidential(commitment));
+        assert_eq!(consumed, 33);
+        assert_eq!(confidential.to_bytes(), commitment);
+
+        assert_eq!(
+            ConfidentialAmount::from_bytes(&[0x05]),
+            Err(BitcoinError::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn test_non_final_inputs() {
+        let input = |sequence| {
+            TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![]),
+                sequence,
+            )
+        };
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![input(0xFFFFFFFF), input(0xFFFFFFFE), input(0xFFFFFFFF)],
+            800_000,
+        );
+        assert_eq!(tx.non_final_inputs(), vec![1]);
+    }
+
+    #[test]
+    fn test_fee_for_target() {
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x00; 50]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        let estimator = |target: u16| if target <= 2 { 12.5 } else { 3.0 };
+        assert_eq!(tx.fee_for_target(1, estimator), 1_250);
+        assert_eq!(tx.fee_for_target(6, estimator), 300);
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let spend = |outpoints: Vec<OutPoint>| {
+            let inputs = outpoints
+                .into_iter()
+                .map(|outpoint| TransactionInput::new(outpoint, Script::new(vec![]), 0xFFFFFFFF))
+                .collect();
+            BitcoinTransaction::new(2, inputs, 0)
+        };
+        let shared = OutPoint::new(dummy_txid(1), 0);
+        let txs = vec![
+            spend(vec![shared.clone(), OutPoint::new(dummy_txid(2), 0)]),
+            spend(vec![OutPoint::new(dummy_txid(3), 0)]),
+            spend(vec![OutPoint::new(dummy_txid(4), 1), shared.clone()]),
+        ];
+        assert_eq!(find_conflicts(&txs), vec![(0, 2, shared.clone())]);
+
+        let txs = vec![
+            spend(vec![shared.clone()]),
+            spend(vec![shared.clone(), shared.clone()]),
+            spend(vec![shared.clone()]),
+        ];
+        assert_eq!(
+            find_conflicts(&txs),
+            vec![
+                (0, 1, shared.clone()),
+                (0, 2, shared.clone()),
+                (1, 2, shared)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_outpoint_list() {
+        let outpoints = vec![
+            OutPoint::new(dummy_txid(1), 0),
+            OutPoint::new(dummy_txid(2), 1),
+            OutPoint::new(dummy_txid(3), 2),
+        ];
+        let mut bytes = vec![0x03];
+        for outpoint in &outpoints {
+            bytes.extend(outpoint.to_bytes());
+        }
+        let (parsed, consumed) = parse_outpoint_list(&bytes).unwrap();
+        assert_eq!(parsed, outpoints);
+        assert_eq!(consumed, 1 + 3 * 36);
+        assert_eq!(BitcoinSerialize::to_bytes(&parsed), bytes);
+    }
+
+    #[test]
+    fn test_script_count_signatures() {
+        let script_sig = Script::new_p2sh_multisig_scriptsig(
+            &[dummy_signature(71), dummy_signature(72)],
+            &dummy_multisig_redeem_script(),
+        );
+        assert_eq!(script_sig.count_signatures(), 2);
+
+        let partial = Script::new_p2sh_multisig_scriptsig(
+            &[dummy_signature(70)],
+            &Script::new(vec![0x51, 0xAE]),
+        );
+        assert_eq!(partial.count_signatures(), 1);
+
+        let mut not_push_only = vec![0x47];
+        not_push_only.extend(dummy_signature(71));
+        not_push_only.push(0xAC);
+        assert_eq!(Script::new(not_push_only).count_signatures(), 0);
+    }
+
+    #[test]
+    fn test_canonical_sorted() {
+        let mut high_first_byte = [0u8; 32];
+        high_first_byte[0] = 0xFF;
+        let a = TransactionInput::new(OutPoint::new(dummy_txid(1), 1), Script::new(vec![]), 0);
+        let b = TransactionInput::new(OutPoint::new(dummy_txid(1), 0), Script::new(vec![]), 0);
+        let c = TransactionInput::new(OutPoint::new(high_first_byte, 0), Script::new(vec![]), 0);
+        let d = TransactionInput::new(OutPoint::new(dummy_txid(2), 0), Script::new(vec![]), 0);
+
+        let tx = BitcoinTransaction::new(2, vec![d.clone(), a.clone(), c.clone(), b.clone()], 0);
+        let reordered =
+            BitcoinTransaction::new(2, vec![b.clone(), c.clone(), a.clone(), d.clone()], 0);
+        assert_ne!(tx, reordered);
+        assert_eq!(tx.canonical_sorted(), reordered.canonical_sorted());
+        assert_eq!(tx.canonical_sorted().inputs, vec![c, b, a, d]);
+    }
+
+    #[test]
+    fn test_input_offset() {
+        let inputs: Vec<_> = (0..3)
+            .map(|i| {
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(i), i as u32),
+                    Script::new(vec![0xA0; 10 * i as usize]),
+                    0xFFFFFFFF,
+                )
+            })
+            .collect();
+        let tx = BitcoinTransaction::new(2, inputs, 0);
+        let bytes = tx.to_bytes();
+        for (index, input) in tx.inputs.iter().enumerate() {
+            let offset = tx.input_offset(index).unwrap();
+            let (parsed, _) = TransactionInput::from_bytes(&bytes[offset..]).unwrap();
+            assert_eq!(&parsed, input);
+        }
+        assert_eq!(tx.input_offset(0), Ok(5));
+        assert_eq!(tx.input_offset(3), Err(BitcoinError::InvalidInputIndex));
+    }
+
+    #[test]
+    fn test_write_to_with_progress() {
+        let inputs: Vec<_> = (0..3)
+            .map(|i| {
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(i), 0),
+                    Script::new(vec![0x01; 20]),
+                    0xFFFFFFFF,
+                )
+            })
+            .collect();
+        let tx = BitcoinTransaction::new(2, inputs, 0);
+        let mut written = Vec::new();
+        let mut reports = Vec::new();
+        tx.write_to_with_progress(&mut written, |bytes| reports.push(bytes))
+            .unwrap();
+        assert_eq!(written, tx.to_bytes());
+        assert_eq!(reports.len(), 5);
+        assert_eq!(reports.iter().sum::<usize>(), written.len());
+    }
+
+    #[test]
+    fn test_wallet_fingerprint() {
+        let signed_input = |txid: u8, signature: Vec<u8>, sequence| {
+            let mut script_sig = vec![signature.len() as u8];
+            script_sig.extend(signature);
+            TransactionInput::new(
+                OutPoint::new(dummy_txid(txid), 0),
+                Script::new(script_sig),
+                sequence,
+            )
+        };
+
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![
+                signed_input(1, dummy_signature(71), 0xFFFFFFFD),
+                signed_input(2, dummy_signature(70), 0xFFFFFFFD),
+            ],
+            850_000,
+        );
+        assert_eq!(
+            tx.wallet_fingerprint(),
+            WalletHints {
+                height_lock_time: true,
+                bip69_inputs: true,
+                signals_rbf: true,
+                low_r_signatures: true,
+                uniform_sequences: true,
+            }
+        );
+
+        let tx = BitcoinTransaction::new(
+            1,
+            vec![
+                signed_input(2, dummy_signature(72), 0xFFFFFFFF),
+                signed_input(1, dummy_signature(71), 0xFFFFFFFE),
+            ],
+            0,
+        );
+        assert_eq!(tx.wallet_fingerprint(), WalletHints::default());
+    }
+
+    #[test]
+    fn test_select_inputs() {
+        let utxos = vec![
+            (OutPoint::new(dummy_txid(1), 0), 20_000),
+            (OutPoint::new(dummy_txid(2), 0), 50_000),
+            (OutPoint::new(dummy_txid(3), 0), 500),
+            (OutPoint::new(dummy_txid(4), 0), 30_000),
+        ];
+        // 272 WU at 10 sat/vB costs 680 sats per input.
+        assert_eq!(
+            select_inputs(&utxos, 70_000, 10.0, 272),
+            Some(vec![
+                OutPoint::new(dummy_txid(2), 0),
+                OutPoint::new(dummy_txid(4), 0),
+            ])
+        );
+        assert_eq!(
+            select_inputs(&utxos, 79_000, 10.0, 272),
+            Some(vec![
+                OutPoint::new(dummy_txid(2), 0),
+                OutPoint::new(dummy_txid(4), 0),
+                OutPoint::new(dummy_txid(1), 0),
+            ])
+        );
+        assert_eq!(select_inputs(&utxos, 99_000, 10.0, 272), None);
+    }
+
+    #[test]
+    fn test_vsize_without_input() {
+        let inputs: Vec<_> = (0..253)
+            .map(|i| {
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(i as u8), i),
+                    Script::new(vec![0x01; (i % 5) as usize]),
+                    0xFFFFFFFF,
+                )
+            })
+            .collect();
+        let tx = BitcoinTransaction::new(2, inputs, 0);
+        for index in [0, 7, 252] {
+            let mut removed = tx.clone();
+            removed.inputs.remove(index);
+            assert_eq!(tx.vsize_without_input(index), Ok(removed.vsize()));
+        }
+        assert_eq!(
+            tx.vsize_without_input(253),
+            Err(BitcoinError::InvalidInputIndex)
+        );
+    }
+
+    #[test]
+    fn test_var_bytes_roundtrip() {
+        for data in [vec![], vec![0x5A; 300]] {
+            let mut buf = Vec::new();
+            write_var_bytes(&data, &mut buf);
+            let (parsed, consumed) = read_var_bytes(&buf).unwrap();
+            assert_eq!(parsed, data);
+            assert_eq!(consumed, buf.len());
+        }
+
+        let mut buf = Vec::new();
+        write_var_bytes(&[0x5A; 300], &mut buf);
+        assert_eq!(&buf[..3], &[0xFD, 0x2C, 0x01]);
+        assert_eq!(
+            read_var_bytes(&buf[..100]),
+            Err(BitcoinError::InsufficientBytes)
+        );
+    }
+
+    #[test]
+    fn test_bitcoin_tx_from_hex_multiline() {
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x01, 0x02]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        let hex_str = tx.to_hex();
+        let multiline = format!(
+            "  {}\n{}\r\n\t{} ",
+            &hex_str[..20],
+            &hex_str[20..60],
+            &hex_str[60..]
+        );
+        assert_eq!(BitcoinTransaction::from_hex(&multiline), Ok(tx));
+
+        let with_junk = format!("{}-{}", &hex_str[..20], &hex_str[20..]);
+        assert_eq!(
+            BitcoinTransaction::from_hex(&with_junk),
+            Err(BitcoinError::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn test_expected_witness_weight() {
+        assert_eq!(expected_witness_weight(ScriptType::P2WPKH), 108);
+        assert_eq!(expected_witness_weight(ScriptType::P2TR), 66);
+        assert_eq!(expected_witness_weight(ScriptType::P2A), 1);
+        assert_eq!(expected_witness_weight(ScriptType::P2PKH), 0);
+        assert_eq!(expected_witness_weight(ScriptType::P2SH), 0);
+    }
+
+    #[test]
+    fn test_to_bytes_with_options() {
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![TransactionInput::new(
+                OutPoint::new(dummy_txid(1), 0),
+                Script::new(vec![0x01, 0x02]),
+                0xFFFFFFFF,
+            )],
+            0,
+        );
+        assert_eq!(
+            tx.to_bytes_with_options(SerializeOptions::default()),
+            tx.to_bytes()
+        );
+
+        let bytes = tx.to_bytes_with_options(SerializeOptions {
+            non_minimal_counts: true,
+        });
+        assert_eq!(&bytes[4..7], &[0xFD, 0x01, 0x00]);
+        assert!(!CompactSize::is_canonical(&bytes[4..7]));
+        assert_eq!(&bytes[43..46], &[0xFD, 0x02, 0x00]);
+        assert!(!CompactSize::is_canonical(&bytes[43..46]));
+
+        // The lenient parser still accepts it; it is only the encoding that differs.
+        let (parsed, consumed) = BitcoinTransaction::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed, tx);
+        assert_eq!(consumed, bytes.len());
+        assert_ne!(bytes, tx.to_bytes());
+    }
+
+    #[test]
+    fn test_referenced_txids() {
+        let input = |txid: u8, vout| {
+            TransactionInput::new(
+                OutPoint::new(dummy_txid(txid), vout),
+                Script::new(vec![]),
+                0,
+            )
+        };
+        let tx = BitcoinTransaction::new(2, vec![input(1, 0), input(2, 0), input(1, 3)], 0);
+        assert_eq!(
+            tx.referenced_txids(),
+            vec![Txid(dummy_txid(1)), Txid(dummy_txid(2))]
+        );
+    }
+
+    #[test]
+    fn test_validate_truc_limits() {
+        let with_script = |version, script_len| {
+            BitcoinTransaction::new(
+                version,
+                vec![TransactionInput::new(
+                    OutPoint::new(dummy_txid(1), 0),
+                    Script::new(vec![0x00; script_len]),
+                    0xFFFFFFFF,
+                )],
+                0,
+            )
+        };
+        let small = with_script(3, 100);
+        assert!(small.is_truc());
+        assert_eq!(small.validate_truc_limits(), Ok(()));
+
+        let large = with_script(3, 10_000);
+        assert!(large.vsize() > TRUC_MAX_VSIZE);
+        assert_eq!(
+            large.validate_truc_limits(),
+            Err(BitcoinError::InvalidFormat)
+        );
+
+        let large_v2 = with_script(2, 10_000);
+        assert!(!large_v2.is_truc());
+        assert_eq!(large_v2.validate_truc_limits(), Ok(()));
+    }
+
+    #[test]
+    fn test_signing_status() {
+        let partially_signed = Script::new_p2sh_multisig_scriptsig(
+            &[dummy_signature(71)],
+            &dummy_multisig_redeem_script(),
+        );
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(1), 0),
+                    partially_signed,
+                    0xFFFFFFFF,
+                ),
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(2), 0),
+                    Script::new(vec![]),
+                    0xFFFFFFFF,
+                ),
+            ],
+            0,
+        );
+        assert_eq!(tx.signing_status(&[2, 1]), vec![(1, 2), (0, 1)]);
+    }
+
+    #[test]
+    fn test_inputs_bytes_roundtrip() {
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(1), 0),
+                    Script::new(vec![0x01, 0x02]),
+                    0xFFFFFFFF,
+                ),
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(2), 4),
+                    Script::new(vec![]),
+                    0xFFFFFFFD,
+                ),
+            ],
+            0,
+        );
+        let bytes = tx.inputs_bytes();
+        assert_eq!(bytes, tx.to_bytes()[4..tx.to_bytes().len() - 4]);
+        let (inputs, consumed) = Vec::<TransactionInput>::from_bytes(&bytes).unwrap();
+        assert_eq!(inputs, tx.inputs);
+        assert_eq!(consumed, bytes.len());
+
+        assert_eq!(
+            Vec::<TransactionInput>::from_bytes(&bytes[..bytes.len() - 2]),
+            Err(BitcoinError::InsufficientBytes)
+        );
+    }
+
+    #[test]
+    fn test_fee_to_beat() {
+        let tx = BitcoinTransaction::new(2, vec![], 0);
+        // 1000 sats for 200 vB; replacement is 150 vB at 1 sat/vB incremental.
+        assert_eq!(tx.fee_to_beat(150, 1_000, 200, 1.0), Some(1_150));
+        // A larger replacement is bound by the competitor's 5 sat/vB feerate.
+        assert_eq!(tx.fee_to_beat(400, 1_000, 200, 1.0), Some(2_001));
+
+        assert_eq!(tx.fee_to_beat(100, u64::MAX, 100, 1.0), None);
+        assert_eq!(
+            tx.fee_to_beat(1_000_000, 2_100_000_000_000_000, 60, 1.0),
+            None
+        );
+        assert_eq!(tx.fee_to_beat(150, 1_000, 0, 1.0), None);
+    }
+
+    #[test]
+    fn test_iter_transactions() {
+        let txs: Vec<_> = (0..3)
+            .map(|i| {
+                BitcoinTransaction::new(
+                    2,
+                    vec![TransactionInput::new(
+                        OutPoint::new(dummy_txid(i), 0),
+                        Script::new(vec![0x01; i as usize]),
+                        0xFFFFFFFF,
+                    )],
+                    i as u32,
+                )
+            })
+            .collect();
+        let mut bytes = Vec::new();
+        let mut offsets = Vec::new();
+        for tx in &txs {
+            offsets.push(bytes.len());
+            bytes.extend(tx.to_bytes());
+        }
+
+        let parsed: Vec<_> = iter_transactions(&bytes).collect::<Result<_, _>>().unwrap();
+        assert_eq!(parsed, txs.into_iter().zip(offsets).collect::<Vec<_>>());
+
+        let mut truncated = iter_transactions(&bytes[..bytes.len() - 2]);
+        assert!(truncated.next().unwrap().is_ok());
+        assert!(truncated.next().unwrap().is_ok());
+        assert_eq!(truncated.next(), Some(Err(BitcoinError::InsufficientBytes)));
+        assert_eq!(truncated.next(), None);
+
+        // Cut two bytes into the last input's sequence.
+        let mut truncated = iter_transactions(&bytes[..bytes.len() - 6]);
+        assert!(truncated.next().unwrap().is_ok());
+        assert!(truncated.next().unwrap().is_ok());
+        assert_eq!(truncated.next(), Some(Err(BitcoinError::InsufficientBytes)));
+        assert_eq!(truncated.next(), None);
+    }
+
+    #[test]
+    fn test_check_locktime() {
+        let tx_with = |lock_time, sequences: &[u32]| {
+            let inputs = sequences
+                .iter()
+                .map(|&sequence| {
+                    TransactionInput::new(
+                        OutPoint::new(dummy_txid(1), 0),
+                        Script::new(vec![]),
+                        sequence,
+                    )
+                })
+                .collect();
+            BitcoinTransaction::new(2, inputs, lock_time)
+        };
+
+        let height_locked = tx_with(800_000, &[0xFFFFFFFF, 0xFFFFFFFE]);
+        assert_eq!(height_locked.check_locktime(800_001, 0), Ok(()));
+        assert_eq!(
+            height_locked.check_locktime(800_000, 2_000_000_000),
+            Err(BitcoinError::LockTimeNotSatisfied {
+                lock_time: 800_000,
+                input_index: 1
+            })
+        );
+
+        let time_locked = tx_with(1_700_000_000, &[0xFFFFFFFD]);
+        assert_eq!(time_locked.check_locktime(0, 1_700_000_001), Ok(()));
+        assert!(time_locked.check_locktime(900_000, 1_700_000_000).is_err());
+
+        let all_final = tx_with(1_700_000_000, &[0xFFFFFFFF]);
+        assert_eq!(all_final.check_locktime(0, 0), Ok(()));
+    }
+
+    #[test]
+    fn test_check_sequence_locks() {
+        let tx = BitcoinTransaction::new(
+            2,
+            vec![
+                TransactionInput::new(OutPoint::new(dummy_txid(1), 0), Script::new(vec![]), 10),
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(2), 0),
+                    Script::new(vec![]),
+                    (1 << 22) | 2,
+                ),
+                TransactionInput::new(
+                    OutPoint::new(dummy_txid(3), 0),
+                    Script::new(vec![]),
+                    0xFFFFFFFF,
+                ),
+            ],
+            0,
+        );
+        let heights = [100, 100, 100];
+        let mtps = [1_000_000, 1_000_000, 1_000_000];
+
+        assert_eq!(
+            tx.check_sequence_locks(&heights, &mtps, 110, 1_001_024),
+            Ok(())
+        );
+        assert_eq!(
+            tx.check_sequence_locks(&heights, &mtps, 109, 1_001_024),
+            Err(BitcoinError::SequenceLockNotSatisfied { input_index: 0 })
+        );
+        assert_eq!(
+            tx.check_sequence_locks(&heights, &mtps, 110, 1_001_023),
+            Err(BitcoinError::SequenceLockNotSatisfied { input_index: 1 })
+        );
+        assert_eq!(
+            tx.check_sequence_locks(&heights[..2], &mtps, 110, 1_001_024),
+            Err(BitcoinError::InvalidFormat)
+        );
+
+        let mut v1 = tx.clone();
+        v1.version = 1;
+        assert_eq!(v1.check_sequence_locks(&heights, &mtps, 0, 0), Ok(()));
+    }
 }